@@ -0,0 +1,73 @@
+use std::sync::Arc;
+
+use crate::error::DomainError;
+
+pub mod equations;
+pub mod fractions;
+
+/// Parameters controlling problem generation: how hard the instance
+/// should be, which operations it may draw on, and the range of values
+/// to sample from. Each built-in domain consults `allowed_ops` to decide
+/// which terms it's allowed to generate (e.g. dropping the coefficient
+/// term when `"*"` isn't listed); domains with no matching operation can
+/// ignore the field. `Fractions` does not consult `value_range`: its
+/// numerators and denominators are drawn from `1..=4 + 3 * difficulty`.
+#[derive(Clone)]
+pub struct GenConfig {
+    pub difficulty: u32,
+    pub allowed_ops: Vec<String>,
+    pub value_range: (i64, i64),
+}
+
+impl GenConfig {
+    /// A reasonable default config for a given difficulty level: wider
+    /// value ranges and no restriction on operations as difficulty rises.
+    pub fn for_difficulty(difficulty: u32) -> GenConfig {
+        let bound = 10 + (difficulty as i64) * 10;
+        GenConfig {
+            difficulty,
+            allowed_ops: vec!["+".to_string(), "-".to_string(), "*".to_string(), "/".to_string()],
+            value_range: (-bound, bound),
+        }
+    }
+}
+
+/// A single action available from a state, pairing the resulting state
+/// with a formal (symbolic) and a human-readable description of the
+/// transformation that produced it, plus the reward earned and whether
+/// the resulting state is terminal.
+#[derive(Clone, Serialize)]
+pub struct Action {
+    pub next_state: String,
+    #[serde(rename = "formal")]
+    pub formal_description: String,
+    #[serde(rename = "human")]
+    pub human_description: String,
+    pub reward: f64,
+    pub done: bool,
+}
+
+/// A problem domain: generates problem instances and enumerates the
+/// legal next steps from any state.
+pub trait Domain: Send + Sync {
+    /// Generates a fresh problem instance from the given random seed,
+    /// shaped by `config` (difficulty, allowed operations, value range).
+    fn generate(&self, seed: u64, config: &GenConfig) -> Result<String, DomainError>;
+
+    /// Returns the legal actions from `state`, or a `DomainError` if
+    /// `state` fails to parse.
+    fn step(&self, state: String) -> Result<Vec<Action>, DomainError>;
+
+    /// Returns whether `state` is a solved terminal state.
+    fn is_solved(&self, state: &str) -> bool;
+
+    /// Returns the reward for transitioning from `from` to `to`, so each
+    /// domain can shape its own reward signal (e.g. a bonus on reaching
+    /// a solved state, a small penalty otherwise).
+    fn reward(&self, from: &str, to: &str) -> f64;
+
+    /// Renders `state` as typeset LaTeX, or `None` if it fails to parse.
+    fn to_latex(&self, state: &str) -> Option<String>;
+}
+
+pub type DomainRef = Arc<dyn Domain>;
@@ -0,0 +1,258 @@
+use num_rational::Ratio;
+use pest::Parser;
+
+use crate::domain::{Action, Domain, GenConfig};
+use crate::error::DomainError;
+
+type Rational = Ratio<i64>;
+
+#[derive(Parser)]
+#[grammar = "domain/equations.pest"]
+struct EquationParser;
+
+/// A single-variable linear equation in `x`, represented internally as
+/// `coef * x + constant` on each side.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Side {
+    coef: Rational,
+    constant: Rational,
+}
+
+/// The `equations-ct` domain: single-variable linear equations solved by
+/// the classic "collect terms, then divide" moves.
+pub struct Equations {}
+
+impl Equations {
+    fn parse(state: &str) -> Result<(Side, Side), DomainError> {
+        let mut pairs = EquationParser::parse(Rule::equation, state).map_err(|e| DomainError::StateParse {
+            state: state.to_string(),
+            location: format!("{:?}", e.line_col),
+        })?;
+        let equation = pairs.next().unwrap();
+        let mut sides = equation.into_inner().filter(|p| p.as_rule() == Rule::side);
+        let lhs = Self::parse_side(state, sides.next().unwrap())?;
+        let rhs = Self::parse_side(state, sides.next().unwrap())?;
+        Ok((lhs, rhs))
+    }
+
+    /// Parses an `integer` or `fraction` token into an exact `Rational`.
+    /// Both sides of a `step` division are formatted through this same
+    /// `fraction` grammar rule, so a non-integer solution (e.g. `3/2`)
+    /// round-trips instead of landing in an unparseable dead end.
+    fn parse_rational(state: &str, token: &pest::iterators::Pair<Rule>) -> Result<Rational, DomainError> {
+        let s = token.as_str();
+        let bad = |part: &str| DomainError::StateParse {
+            state: state.to_string(),
+            location: format!("number {:?} out of range", part),
+        };
+        match s.split_once('/') {
+            Some((num, den)) => {
+                let n: i64 = num.parse().map_err(|_| bad(s))?;
+                let d: i64 = den.parse().map_err(|_| bad(s))?;
+                if d == 0 {
+                    return Err(DomainError::StateParse {
+                        state: state.to_string(),
+                        location: format!("zero denominator in {:?}", s),
+                    });
+                }
+                Ok(Rational::new(n, d))
+            }
+            None => s.parse().map(Rational::from).map_err(|_| bad(s)),
+        }
+    }
+
+    fn parse_side(state: &str, side: pest::iterators::Pair<Rule>) -> Result<Side, DomainError> {
+        let mut coef = Rational::from(0);
+        let mut constant = Rational::from(0);
+        let mut sign = Rational::from(1);
+        for token in side.into_inner() {
+            match token.as_rule() {
+                Rule::term => {
+                    let mut inner = token.into_inner();
+                    let first = inner.next().unwrap();
+                    match first.as_rule() {
+                        Rule::integer if inner.clone().next().is_some() => {
+                            // `integer ~ "*" ~ variable`
+                            let n = Self::parse_rational(state, &first)?;
+                            coef += sign * n;
+                        }
+                        Rule::integer | Rule::fraction => {
+                            let n = Self::parse_rational(state, &first)?;
+                            constant += sign * n;
+                        }
+                        Rule::variable => {
+                            coef += sign;
+                        }
+                        _ => unreachable!(),
+                    }
+                    sign = Rational::from(1);
+                }
+                _ if token.as_str() == "+" => sign = Rational::from(1),
+                _ if token.as_str() == "-" => sign = Rational::from(-1),
+                _ => {}
+            }
+        }
+        Ok(Side { coef, constant })
+    }
+
+    fn format(lhs: Side, rhs: Side) -> String {
+        format!(
+            "{}*x{}={}",
+            lhs.coef,
+            Self::format_constant(lhs.constant),
+            rhs.constant
+        )
+    }
+
+    fn format_constant(c: Rational) -> String {
+        if c == Rational::from(0) {
+            String::new()
+        } else if c > Rational::from(0) {
+            format!("+{}", c)
+        } else {
+            format!("{}", c)
+        }
+    }
+
+    fn render_latex_rational(v: Rational) -> String {
+        if *v.denom() == 1 {
+            format!("{}", v.numer())
+        } else {
+            format!("\\frac{{{}}}{{{}}}", v.numer(), v.denom())
+        }
+    }
+
+    fn render_latex_side(side: Side) -> String {
+        let mut rendered = if side.coef == Rational::from(1) {
+            "x".to_string()
+        } else if side.coef == Rational::from(-1) {
+            "-x".to_string()
+        } else if side.coef == Rational::from(0) {
+            String::new()
+        } else {
+            format!("{}x", Self::render_latex_rational(side.coef))
+        };
+
+        if side.constant != Rational::from(0) || rendered.is_empty() {
+            if side.constant > Rational::from(0) && !rendered.is_empty() {
+                rendered.push_str(" + ");
+            } else if side.constant < Rational::from(0) {
+                rendered.push_str(" - ");
+            }
+            let magnitude = if side.constant < Rational::from(0) { -side.constant } else { side.constant };
+            rendered.push_str(&Self::render_latex_rational(magnitude));
+        }
+        rendered
+    }
+}
+
+impl Domain for Equations {
+    fn generate(&self, seed: u64, config: &GenConfig) -> Result<String, DomainError> {
+        // A small linear-congruential generator is enough to turn a u64
+        // seed into a handful of bounded integers; it keeps this domain
+        // dependency-free and fully deterministic for a given seed.
+        let mut state = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+        let mut next = |bound: i64| -> i64 {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+            ((state >> 33) % bound as u64) as i64 - bound / 2
+        };
+        let (lo, hi) = config.value_range;
+        let value_bound = (hi - lo + 1).max(1);
+        let allows = |op: &str| config.allowed_ops.iter().any(|o| o == op);
+
+        // Without "*" there's no coefficient term to divide out; without
+        // "+"/"-" there's no constant term to subtract, so each op that's
+        // disallowed collapses the corresponding generated term.
+        let mut coef = if allows("*") { next((2 + config.difficulty as i64).max(2)) } else { 1 };
+        if coef == 0 {
+            coef = 1;
+        }
+        // `next` is centred on zero; shift it so b and x land in `lo..=hi`.
+        let b = if allows("+") || allows("-") { lo + next(value_bound) + value_bound / 2 } else { 0 };
+        let x = lo + next(value_bound) + value_bound / 2;
+        let c = coef * x + b;
+        Ok(format!("{}*x+{}={}", coef, b, c))
+    }
+
+    fn step(&self, state: String) -> Result<Vec<Action>, DomainError> {
+        let (lhs, rhs) = Self::parse(&state)?;
+        let mut actions = Vec::new();
+
+        if lhs.constant != Rational::from(0) {
+            let new_lhs = Side { coef: lhs.coef, constant: Rational::from(0) };
+            let new_rhs = Side { coef: rhs.coef, constant: rhs.constant - lhs.constant };
+            let next_state = Self::format(new_lhs, new_rhs);
+            actions.push(Action {
+                reward: self.reward(&state, &next_state),
+                done: self.is_solved(&next_state),
+                next_state,
+                formal_description: format!("-{} both sides", lhs.constant),
+                human_description: format!("Subtract {} from both sides", lhs.constant),
+            });
+        } else if lhs.coef != Rational::from(1) && lhs.coef != Rational::from(0) {
+            let new_lhs = Side { coef: Rational::from(1), constant: Rational::from(0) };
+            let new_rhs = Side { coef: rhs.coef, constant: rhs.constant / lhs.coef };
+            let next_state = Self::format(new_lhs, new_rhs);
+            actions.push(Action {
+                reward: self.reward(&state, &next_state),
+                done: self.is_solved(&next_state),
+                next_state,
+                formal_description: format!("/{} both sides", lhs.coef),
+                human_description: format!("Divide both sides by {}", lhs.coef),
+            });
+        }
+
+        Ok(actions)
+    }
+
+    fn is_solved(&self, state: &str) -> bool {
+        match Self::parse(state) {
+            Ok((lhs, _)) => lhs.coef == Rational::from(1) && lhs.constant == Rational::from(0),
+            Err(_) => false,
+        }
+    }
+
+    fn reward(&self, _from: &str, to: &str) -> f64 {
+        if self.is_solved(to) {
+            1.0
+        } else {
+            -0.01
+        }
+    }
+
+    fn to_latex(&self, state: &str) -> Option<String> {
+        let (lhs, rhs) = Self::parse(state).ok()?;
+        Some(format!("{} = {}", Self::render_latex_side(lhs), Self::render_latex_side(rhs)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_then_step_reaches_solved() {
+        let domain = Equations {};
+        let config = GenConfig::for_difficulty(1);
+        let mut state = domain.generate(42, &config).unwrap();
+
+        let mut steps = 0;
+        while !domain.is_solved(&state) {
+            let actions = domain.step(state.clone()).unwrap();
+            assert!(!actions.is_empty(), "state {:?} is unsolved but has no actions", state);
+            state = actions[0].next_state.clone();
+            steps += 1;
+            assert!(steps <= 2, "equations-ct should solve in at most 2 moves, got stuck on {:?}", state);
+        }
+    }
+
+    #[test]
+    fn step_reaches_a_solved_fractional_state() {
+        let domain = Equations {};
+        let actions = domain.step("4*x=6".to_string()).unwrap();
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].next_state, "1*x=3/2");
+        assert!(actions[0].done);
+        assert!(domain.is_solved(&actions[0].next_state));
+    }
+}
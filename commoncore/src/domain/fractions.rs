@@ -0,0 +1,95 @@
+use num_rational::Ratio;
+
+use crate::domain::{Action, Domain, GenConfig};
+use crate::error::DomainError;
+
+type Rational = Ratio<i64>;
+
+/// The `fractions` domain: reduce the sum of two fractions, `a/b+c/d`, to
+/// a single fraction in lowest terms.
+pub struct Fractions {}
+
+impl Fractions {
+    fn parse_fraction(s: &str) -> Option<Rational> {
+        let mut parts = s.splitn(2, '/');
+        let num: i64 = parts.next()?.trim().parse().ok()?;
+        let den: i64 = parts.next()?.trim().parse().ok()?;
+        if den == 0 {
+            return None;
+        }
+        Some(Rational::new(num, den))
+    }
+
+    fn format(r: Rational) -> String {
+        format!("{}/{}", r.numer(), r.denom())
+    }
+
+    fn parse_state(state: &str) -> Result<(Rational, Rational), DomainError> {
+        let parts: Vec<&str> = state.splitn(2, '+').collect();
+        if parts.len() != 2 {
+            return Err(DomainError::StateParse { state: state.to_string(), location: "missing '+'".to_string() });
+        }
+        let lhs = Self::parse_fraction(parts[0])
+            .ok_or_else(|| DomainError::StateParse { state: state.to_string(), location: "lhs".to_string() })?;
+        let rhs = Self::parse_fraction(parts[1])
+            .ok_or_else(|| DomainError::StateParse { state: state.to_string(), location: "rhs".to_string() })?;
+        Ok((lhs, rhs))
+    }
+}
+
+impl Domain for Fractions {
+    fn generate(&self, seed: u64, config: &GenConfig) -> Result<String, DomainError> {
+        let bound = (4 + config.difficulty as i64 * 3).max(2);
+        let mut state = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+        let mut next = |hi: i64| -> i64 {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+            1 + ((state >> 33) % hi as u64) as i64
+        };
+        let (a, b) = (next(bound), next(bound));
+
+        // Without "+" allowed there's nothing to combine, so hand back a
+        // single already-reduced fraction instead of a sum to simplify.
+        if !config.allowed_ops.iter().any(|op| op == "+") {
+            return Ok(Self::format(Rational::new(a, b)));
+        }
+
+        let (c, d) = (next(bound), next(bound));
+        Ok(format!("{}/{}+{}/{}", a, b, c, d))
+    }
+
+    fn step(&self, state: String) -> Result<Vec<Action>, DomainError> {
+        if self.is_solved(&state) {
+            return Ok(Vec::new());
+        }
+        let (lhs, rhs) = Self::parse_state(&state)?;
+        let next_state = Self::format(lhs + rhs);
+        Ok(vec![Action {
+            reward: self.reward(&state, &next_state),
+            done: self.is_solved(&next_state),
+            next_state,
+            formal_description: "combine over common denominator".to_string(),
+            human_description: "Add the fractions by finding a common denominator".to_string(),
+        }])
+    }
+
+    fn is_solved(&self, state: &str) -> bool {
+        !state.contains('+')
+    }
+
+    fn reward(&self, _from: &str, to: &str) -> f64 {
+        if self.is_solved(to) {
+            1.0
+        } else {
+            -0.01
+        }
+    }
+
+    fn to_latex(&self, state: &str) -> Option<String> {
+        if let Ok((lhs, rhs)) = Self::parse_state(state) {
+            Some(format!("\\frac{{{}}}{{{}}} + \\frac{{{}}}{{{}}}", lhs.numer(), lhs.denom(), rhs.numer(), rhs.denom()))
+        } else {
+            let r = Self::parse_fraction(state)?;
+            Some(format!("\\frac{{{}}}{{{}}}", r.numer(), r.denom()))
+        }
+    }
+}
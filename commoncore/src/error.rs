@@ -0,0 +1,59 @@
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::PyErr;
+
+create_exception!(commoncore, UnknownDomainError, PyException);
+create_exception!(commoncore, StateParseError, PyException);
+create_exception!(commoncore, GenerationError, PyException);
+
+/// Why a `Domain` operation failed, independent of how it gets surfaced
+/// to Python.
+#[derive(Debug)]
+pub enum DomainError {
+    /// No domain is registered under `name`; `known` lists the valid keys.
+    UnknownDomain { name: String, known: Vec<String> },
+    /// `state` could not be parsed; `location` says where or why (the pest
+    /// line/column for grammar errors, otherwise a short description).
+    StateParse { state: String, location: String },
+    /// Problem generation failed.
+    Generation { reason: String },
+}
+
+impl From<DomainError> for PyErr {
+    fn from(err: DomainError) -> PyErr {
+        match err {
+            DomainError::UnknownDomain { name, known } => UnknownDomainError::new_err(format!(
+                "Unknown domain {:?}; valid domains are: {}",
+                name,
+                known.join(", ")
+            )),
+            DomainError::StateParse { state, location } => {
+                StateParseError::new_err(format!("Could not parse state {:?} at {}", state, location))
+            }
+            DomainError::Generation { reason } => GenerationError::new_err(reason),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pyo3::Python;
+
+    #[test]
+    fn each_domain_error_maps_to_its_own_exception_type() {
+        Python::with_gil(|py| {
+            let unknown: PyErr =
+                DomainError::UnknownDomain { name: "bogus".to_string(), known: vec!["equations-ct".to_string()] }
+                    .into();
+            assert!(unknown.is_instance_of::<UnknownDomainError>(py));
+
+            let parse: PyErr =
+                DomainError::StateParse { state: "x".to_string(), location: "0:0".to_string() }.into();
+            assert!(parse.is_instance_of::<StateParseError>(py));
+
+            let generation: PyErr = DomainError::Generation { reason: "out of range".to_string() }.into();
+            assert!(generation.is_instance_of::<GenerationError>(py));
+        });
+    }
+}
@@ -1,62 +1,362 @@
+use once_cell::sync::Lazy;
 use pyo3::prelude::*;
-use pyo3::exceptions::PyValueError;
+use pyo3::types::PyDict;
 use pyo3::wrap_pyfunction;
-use std::collections::HashMap;
-use std::sync::Arc;
+use rayon::prelude::*;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, RwLock};
 
 mod domain;
-use crate::domain::Domain;
+mod error;
 use crate::domain::equations::Equations;
+use crate::domain::fractions::Fractions;
+use crate::domain::{Domain, GenConfig};
+use crate::error::{DomainError, GenerationError, StateParseError, UnknownDomainError};
 
 extern crate num_rational;
 extern crate pest;
 #[macro_use]
 extern crate pest_derive;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
+
+/// The global domain registry, built once and shared across all worker
+/// threads so a `py.allow_threads` batch doesn't have to rebuild it. It's
+/// held behind a `RwLock` rather than being truly immutable so existing
+/// domains can be aliased under new names at runtime via
+/// `register_domain` (see that function's docs for what it can't do).
+static DOMAINS: Lazy<RwLock<HashMap<String, Arc<dyn Domain>>>> = Lazy::new(|| {
+    let mut map: HashMap<String, Arc<dyn Domain>> = HashMap::new();
+    map.insert("equations-ct".to_string(), Arc::new(Equations {}));
+    map.insert("fractions".to_string(), Arc::new(Fractions {}));
+    RwLock::new(map)
+});
+
+/// An action crossing the FFI boundary as a plain tuple: next state,
+/// formal description, human description, reward, and whether the
+/// resulting state is terminal.
+type ActionTuple = (String, String, String, f64, bool);
+
+fn to_tuple(a: &domain::Action) -> ActionTuple {
+    (a.next_state.clone(), a.formal_description.clone(), a.human_description.clone(), a.reward, a.done)
+}
+
+/// Builds a `GenConfig` for `difficulty`, overriding `allowed_ops` and/or
+/// `value_range` when given so curriculum code can restrict which
+/// operations appear or widen/narrow the sampled values without touching
+/// Rust.
+fn build_config(difficulty: u32, allowed_ops: Option<Vec<String>>, value_range: Option<(i64, i64)>) -> GenConfig {
+    let mut config = GenConfig::for_difficulty(difficulty);
+    if let Some(ops) = allowed_ops {
+        config.allowed_ops = ops;
+    }
+    if let Some(range) = value_range {
+        config.value_range = range;
+    }
+    config
+}
 
-thread_local!{
-    static DOMAINS: HashMap<&'static str, Arc<dyn Domain>> = {
-        let mut map : HashMap<&'static str, Arc<dyn Domain>>  = HashMap::new();
-        map.insert("equations-ct", Arc::new(Equations {}));
-        map
-    };
+/// Generates a problem in the specified domain with the given seed, at
+/// the given difficulty level. `allowed_ops` and `value_range` override
+/// the difficulty preset when given.
+#[pyfunction]
+#[args(allowed_ops = "None", value_range = "None")]
+fn generate(
+    domain: String,
+    seed: u64,
+    difficulty: u32,
+    allowed_ops: Option<Vec<String>>,
+    value_range: Option<(i64, i64)>,
+) -> PyResult<String> {
+    let d = lookup_domain(&domain)?;
+    let config = build_config(difficulty, allowed_ops, value_range);
+    Ok(d.generate(seed, &config)?)
+}
+
+/// Returns the actions and rewards for each given state, processed in
+/// parallel across `states` with the GIL released. A state that fails to
+/// parse yields `None` in its slot rather than failing the whole batch.
+#[pyfunction]
+fn step(py: Python, domain: String, states: Vec<String>) -> PyResult<Vec<Option<Vec<ActionTuple>>>> {
+    let d = lookup_domain(&domain)?;
+    let result: Vec<Option<Vec<ActionTuple>>> = py.allow_threads(|| {
+        states
+            .par_iter()
+            .map(|s| d.step(s.clone()).ok().map(|actions| actions.iter().map(to_tuple).collect()))
+            .collect()
+    });
+    Ok(result)
 }
 
-/// Generates a problem in the specified domain with the given seed.
+/// Returns each transition from `states` as a JSON-encoded list of
+/// `{next_state, formal, human, reward, done, render_latex}` objects,
+/// or `None` per-state if it fails to parse.
 #[pyfunction]
-fn generate(domain: String, seed: u64) -> PyResult<String> {
-    DOMAINS.with(|domains| {
-        if let Some(d) = domains.get(domain.as_str()) {
-            let s = d.generate(seed);
-            Ok(s)
-        } else {
-            Err(PyValueError::new_err(format!("Invalid domain.")))
+fn step_json(domain: String, states: Vec<String>) -> PyResult<Vec<Option<String>>> {
+    let d = lookup_domain(&domain)?;
+    let mut result = Vec::with_capacity(states.len());
+    for s in states.iter() {
+        match d.step(s.clone()) {
+            Ok(actions) => {
+                let json_actions: Vec<serde_json::Value> = actions
+                    .iter()
+                    .map(|a| {
+                        let mut value = serde_json::to_value(a).expect("Action always serializes");
+                        value["render_latex"] = serde_json::to_value(d.to_latex(&a.next_state)).unwrap();
+                        value
+                    })
+                    .collect();
+                let encoded = serde_json::to_string(&json_actions)
+                    .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+                result.push(Some(encoded));
+            }
+            Err(_) => result.push(None),
         }
-    })
+    }
+    Ok(result)
 }
 
-/// Returns the actions and rewards for each given state.
+/// Generates a problem from each seed in `seeds` at the given difficulty
+/// level, processed in parallel with the GIL released. `allowed_ops` and
+/// `value_range` override the difficulty preset when given.
 #[pyfunction]
-fn step(domain: String, states: Vec<String>) -> PyResult<Vec<Option<Vec<(String, String, String)>>>> {
-    DOMAINS.with(|domains| {
-        if let Some(d) = domains.get(domain.as_str()) {
-            let mut result = Vec::with_capacity(states.len());
-            for s in states.iter() {
-                result.push(d.step(s.clone()).map(|v| v.iter().map(|a| (a.next_state.clone(),
-                                                                        a.formal_description.clone(),
-                                                                        a.human_description.clone())).collect()));
+#[args(allowed_ops = "None", value_range = "None")]
+fn generate_batch(
+    py: Python,
+    domain: String,
+    seeds: Vec<u64>,
+    difficulty: u32,
+    allowed_ops: Option<Vec<String>>,
+    value_range: Option<(i64, i64)>,
+) -> PyResult<Vec<String>> {
+    let d = lookup_domain(&domain)?;
+    let config = build_config(difficulty, allowed_ops, value_range);
+    let result: Result<Vec<String>, DomainError> =
+        py.allow_threads(|| seeds.par_iter().map(|&seed| d.generate(seed, &config)).collect());
+    Ok(result?)
+}
+
+/// Registers the domain already known as `base` under a new name `name`.
+///
+/// This only aliases an existing, compiled-in `Domain` impl under a new
+/// registry key (e.g. so curriculum code can name an easier preset) — it
+/// cannot install a genuinely new engine from Python, since `Domain` is a
+/// Rust trait with no dynamic/Python-backed implementation. Shipping a
+/// new problem type still requires adding a `Domain` impl in Rust (as
+/// `Fractions` does) and registering it in `DOMAINS` at startup. What IS
+/// fully configurable from Python without recompiling is a registered
+/// domain's own generator: `generate`, `generate_batch`, and `Environment`
+/// all take `allowed_ops`/`value_range` overrides on top of `difficulty`.
+#[pyfunction]
+fn register_domain(name: String, base: String) -> PyResult<()> {
+    let d = lookup_domain(&base)?;
+    DOMAINS.write().unwrap().insert(name, d);
+    Ok(())
+}
+
+/// Lists the names of all currently registered domains.
+#[pyfunction]
+fn list_domains() -> Vec<String> {
+    DOMAINS.read().unwrap().keys().cloned().collect()
+}
+
+/// Finds the shortest sequence of actions from `state` to a solved state
+/// by breadth-first search over the action graph induced by `Domain::step`,
+/// or `None` if no solution is found within `max_depth` expansions.
+fn solve_actions(
+    d: &Arc<dyn Domain>,
+    state: &str,
+    max_depth: usize,
+) -> Option<Vec<ActionTuple>> {
+    if d.is_solved(state) {
+        return Some(Vec::new());
+    }
+
+    let mut queue: VecDeque<String> = VecDeque::new();
+    let mut visited: HashMap<String, (String, ActionTuple)> = HashMap::new();
+    queue.push_back(state.to_string());
+    visited.insert(state.to_string(), (String::new(), (String::new(), String::new(), String::new(), 0.0, false)));
+
+    let mut expansions = 0;
+    while let Some(cur) = queue.pop_front() {
+        if expansions >= max_depth {
+            return None;
+        }
+        expansions += 1;
+
+        let actions = match d.step(cur.clone()) {
+            Ok(a) => a,
+            Err(_) => continue,
+        };
+        for a in actions.iter() {
+            if visited.contains_key(&a.next_state) {
+                continue;
+            }
+            visited.insert(a.next_state.clone(), (cur.clone(), to_tuple(a)));
+            if d.is_solved(&a.next_state) {
+                // Reconstruct the path by walking parent pointers back to the root.
+                let mut path = vec![to_tuple(a)];
+                let mut parent = cur.clone();
+                while let Some((grandparent, action)) = visited.get(&parent) {
+                    if grandparent.is_empty() {
+                        break;
+                    }
+                    path.push(action.clone());
+                    parent = grandparent.clone();
+                }
+                path.reverse();
+                return Some(path);
             }
-            Ok(result)
-        } else {
-            Err(PyValueError::new_err(format!("Invalid domain.")))
+            queue.push_back(a.next_state.clone());
         }
+    }
+
+    None
+}
+
+/// Finds the shortest solution path from `state` in `domain`, as a list
+/// of `(next_state, formal_description, human_description, reward, done)`
+/// actions, or `None` if no solution exists within `max_depth` expansions.
+#[pyfunction]
+fn solve(domain: String, state: String, max_depth: usize) -> PyResult<Option<Vec<ActionTuple>>> {
+    let d = lookup_domain(&domain)?;
+    Ok(solve_actions(&d, &state, max_depth))
+}
+
+/// Returns the first action of the shortest solution from `state`, i.e.
+/// the move a tutor should suggest next, or `None` if no solution is
+/// found within `max_depth` expansions.
+#[pyfunction]
+fn hint(domain: String, state: String, max_depth: usize) -> PyResult<Option<ActionTuple>> {
+    let d = lookup_domain(&domain)?;
+    Ok(solve_actions(&d, &state, max_depth).and_then(|path| path.into_iter().next()))
+}
+
+fn lookup_domain(domain: &str) -> Result<Arc<dyn Domain>, DomainError> {
+    let domains = DOMAINS.read().unwrap();
+    domains.get(domain).cloned().ok_or_else(|| DomainError::UnknownDomain {
+        name: domain.to_string(),
+        known: domains.keys().cloned().collect(),
     })
 }
 
+/// A stateful RL-style wrapper around a `Domain`: it owns the current
+/// problem state so callers can drive a rollout with `step`/`reset`
+/// instead of threading the state string back through Python by hand.
+#[pyclass]
+struct Environment {
+    domain: String,
+    d: Arc<dyn Domain>,
+    config: GenConfig,
+    state: String,
+    episode_length: u32,
+}
+
+#[pymethods]
+impl Environment {
+    #[new]
+    #[args(difficulty = "0", allowed_ops = "None", value_range = "None")]
+    fn new(
+        domain: String,
+        seed: u64,
+        difficulty: u32,
+        allowed_ops: Option<Vec<String>>,
+        value_range: Option<(i64, i64)>,
+    ) -> PyResult<Self> {
+        let d = lookup_domain(&domain)?;
+        let config = build_config(difficulty, allowed_ops, value_range);
+        let state = d.generate(seed, &config)?;
+        Ok(Environment { domain, d, config, state, episode_length: 0 })
+    }
+
+    /// Generates a fresh problem instance and resets the episode.
+    fn reset(&mut self, seed: u64) -> PyResult<String> {
+        self.state = self.d.generate(seed, &self.config)?;
+        self.episode_length = 0;
+        Ok(self.state.clone())
+    }
+
+    /// Returns the current state string.
+    fn current_state(&self) -> String {
+        self.state.clone()
+    }
+
+    /// Returns the `(next_state, formal_description, human_description,
+    /// reward, done)` tuples available from the current state.
+    fn actions(&self) -> PyResult<Vec<ActionTuple>> {
+        let actions = self.d.step(self.state.clone())?;
+        Ok(actions.iter().map(to_tuple).collect())
+    }
+
+    /// Applies `action_index` from the current state, advancing it in
+    /// place, and returns `(next_state, reward, done, info)`.
+    fn step(&mut self, py: Python, action_index: usize) -> PyResult<(String, f64, bool, PyObject)> {
+        let actions = self.d.step(self.state.clone())?;
+        let action = actions
+            .get(action_index)
+            .ok_or_else(|| pyo3::exceptions::PyIndexError::new_err("Invalid action index."))?;
+
+        self.state = action.next_state.clone();
+        self.episode_length += 1;
+
+        let reward = action.reward;
+        let done = action.done;
+
+        let info = PyDict::new(py);
+        info.set_item("domain", self.domain.clone())?;
+        info.set_item("episode_length", self.episode_length)?;
+
+        Ok((self.state.clone(), reward, done, info.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solve_finds_shortest_path_to_a_solved_state() {
+        let d = lookup_domain("equations-ct").unwrap();
+        // 3*x+2=11 takes exactly two moves: subtract 2, then divide by 3.
+        let path = solve_actions(&d, "3*x+2=11", 10).expect("equations-ct should be solvable");
+        assert_eq!(path.len(), 2);
+        assert!(d.is_solved(&path.last().unwrap().0));
+    }
+
+    #[test]
+    fn solve_actions_returns_none_past_max_depth() {
+        let d = lookup_domain("equations-ct").unwrap();
+        assert!(solve_actions(&d, "3*x+2=11", 0).is_none());
+    }
+
+    #[test]
+    fn solve_finds_a_fractional_solution() {
+        let d = lookup_domain("equations-ct").unwrap();
+        // 4*x=6 has no integer solution; the solved state is 1*x=3/2.
+        let path = solve_actions(&d, "4*x=6", 10).expect("a fractional answer should still be solvable");
+        assert_eq!(path.len(), 1);
+        assert_eq!(path[0].0, "1*x=3/2");
+    }
+}
+
 /// A Python module implemented in Rust.
 #[pymodule]
 fn commoncore(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(generate, m)?)?;
+    m.add_function(wrap_pyfunction!(generate_batch, m)?)?;
     m.add_function(wrap_pyfunction!(step, m)?)?;
+    m.add_function(wrap_pyfunction!(step_json, m)?)?;
+    m.add_function(wrap_pyfunction!(solve, m)?)?;
+    m.add_function(wrap_pyfunction!(hint, m)?)?;
+    m.add_function(wrap_pyfunction!(register_domain, m)?)?;
+    m.add_function(wrap_pyfunction!(list_domains, m)?)?;
+    m.add_class::<Environment>()?;
+
+    m.add("UnknownDomainError", _py.get_type::<UnknownDomainError>())?;
+    m.add("StateParseError", _py.get_type::<StateParseError>())?;
+    m.add("GenerationError", _py.get_type::<GenerationError>())?;
 
     Ok(())
 }
\ No newline at end of file